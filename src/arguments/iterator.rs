@@ -1,6 +1,9 @@
 use super::disk_buffer::*;
 use super::errors::{FileErr, InputIteratorErr};
+use memchr::memchr_iter;
+use std::cmp;
 use std::path::{Path, PathBuf};
+use std::str;
 
 /// The `InputIterator` tracks the total number of arguments, the current argument counter, and
 /// takes ownership of an `InputBuffer` which buffers input arguments from the disk when arguments
@@ -11,12 +14,20 @@ pub struct InputIterator {
     input_buffer:        InputBuffer,
 }
 
+/// Upper bound on how many times `buffer()` scans for a delimiter, growing in between, so an
+/// unterminated file can't grow the buffer without bound. The disk buffer's capacity is doubled
+/// at most `MAX_GROWTH_ATTEMPTS - 1` times, since no growth follows the final scan.
+const MAX_GROWTH_ATTEMPTS: u32 = 16;
+
 impl InputIterator {
-    pub fn new(path: &Path, args: usize) -> Result<InputIterator, FileErr> {
+    /// `delimiter` is `b'\n'` for line-based input or `b'\0'` for NUL-separated input (the
+    /// `find -print0 | parallel -0` pipeline); callers are responsible for passing the byte that
+    /// corresponds to whatever `--null`/`-0` flag the CLI argument parser exposes.
+    pub fn new(path: &Path, args: usize, delimiter: u8) -> Result<InputIterator, FileErr> {
         // Create an `InputBuffer` from the unprocessed file.
         let disk_buffer = try!(DiskBuffer::new(path).read()
             .map_err(|why| FileErr::Open(PathBuf::from(path), why)));
-        let input_buffer = try!(InputBuffer::new(disk_buffer));
+        let input_buffer = try!(InputBuffer::new(disk_buffer, delimiter));
 
         Ok(InputIterator {
             total_arguments:   args,
@@ -28,24 +39,47 @@ impl InputIterator {
     fn buffer(&mut self) -> Result<(), InputIteratorErr> {
         // Read the next set of arguments from the unprocessed file, but only read as many bytes
         // as the buffer can hold without overwriting the unused bytes that was shifted to the left.
+        // This is issued exactly once per call: on growth (below), `grow()` alone is responsible
+        // for pulling in more bytes, since re-issuing `buffer()` against a still-small capacity
+        // would shift/discard the unterminated record we've already read and corrupt it.
         try!(self.input_buffer.disk_buffer.buffer(self.input_buffer.capacity).map_err(|why| {
             InputIteratorErr::FileRead(PathBuf::from(self.input_buffer.disk_buffer.path.clone()), why)
         }));
-        let bytes_read = self.input_buffer.disk_buffer.capacity;
-
-        // Update the recorded number of arguments and indices.
         self.input_buffer.start = self.input_buffer.end + 1;
-        self.input_buffer.count_arguments(bytes_read);
-        self.input_buffer.index = 0;
-        Ok(())
-    }
-}
 
-// Implement the `Iterator` trait for `InputIterator` to gain access to all the `Iterator` methods for free.
-impl Iterator for InputIterator {
-    type Item = Result<String, InputIteratorErr>;
+        for attempt in 0..MAX_GROWTH_ATTEMPTS {
+            let bytes_read = self.input_buffer.disk_buffer.capacity;
+            self.input_buffer.count_arguments(bytes_read);
+            self.input_buffer.index = 0;
 
-    fn next(&mut self) -> Option<Result<String, InputIteratorErr>> {
+            let found_delimiter = self.input_buffer.indices.len() > 1;
+            let filled_buffer = bytes_read == self.input_buffer.disk_buffer.data.len();
+            if found_delimiter || !filled_buffer {
+                // Either a delimiter was found, or the whole file fit without one (last record).
+                return Ok(());
+            }
+
+            if attempt + 1 == MAX_GROWTH_ATTEMPTS {
+                break;
+            }
+
+            // No delimiter fits within the bytes read so far: the current record is larger than
+            // the buffer. `grow` doubles the reader's capacity and reads more bytes in after what
+            // is already buffered, the way `BufReader` grows, rather than truncating the record.
+            try!(self.input_buffer.disk_buffer.grow().map_err(|why| {
+                InputIteratorErr::FileRead(PathBuf::from(self.input_buffer.disk_buffer.path.clone()), why)
+            }));
+        }
+
+        // Growth hit its ceiling and a delimiter still wasn't found: give up with a clear error
+        // instead of silently corrupting or truncating the record.
+        Err(InputIteratorErr::RecordTooLarge(PathBuf::from(self.input_buffer.disk_buffer.path.clone())))
+    }
+
+    /// Advances to the next argument and hands back the raw bytes pointing directly into the
+    /// disk buffer's data, without allocating. Shared by `next_borrowed` and `next` so the
+    /// index bookkeeping lives in one place regardless of how the caller wants the bytes decoded.
+    fn advance(&mut self) -> Option<Result<&[u8], InputIteratorErr>> {
         if self.curr_argument == self.total_arguments {
             // If all arguments have been depleted, return `None`.
             return None
@@ -66,8 +100,64 @@ impl Iterator for InputIterator {
         self.curr_argument += 1;
         self.input_buffer.index  += 1;
 
-        // Copy the input from the buffer into a `String` and return it
-        Some(Ok(String::from_utf8_lossy(&self.input_buffer.disk_buffer.data[start..end]).into_owned()))
+        Some(Ok(&self.input_buffer.disk_buffer.data[start..end]))
+    }
+
+    /// Borrows the next argument directly out of the disk buffer without allocating, returning a
+    /// slice that lives as long as `self`. The returned slice must be consumed before the next
+    /// call, since advancing the iterator may refill the buffer and invalidate it.
+    ///
+    /// Unlike `next()`, this requires the argument to be valid UTF-8 and errors otherwise, since
+    /// a borrowed `&str` can't losslessly represent replacement characters the way an owned
+    /// `String` can; callers that need to tolerate arbitrary bytes (e.g. `find -print0` output
+    /// under `-0`) should use `next()` instead.
+    pub fn next_borrowed(&mut self) -> Option<Result<&str, InputIteratorErr>> {
+        self.advance().map(|result| {
+            result.and_then(|bytes| str::from_utf8(bytes).map_err(InputIteratorErr::Utf8))
+        })
+    }
+
+    /// Advances the iterator to argument index `n` without dispatching records `0..n`, to
+    /// support resuming an interrupted run (`--resume`) from the last completed job. Records are
+    /// skipped by counting delimiters and repositioning within an already-buffered segment,
+    /// refilling segments as needed, rather than materializing and discarding each `String`.
+    pub fn seek(&mut self, n: usize) -> Result<(), InputIteratorErr> {
+        if n > self.total_arguments {
+            return Err(InputIteratorErr::SeekOutOfRange(n, self.total_arguments));
+        }
+
+        while self.curr_argument < n {
+            if self.curr_argument == self.input_buffer.end {
+                try!(self.buffer());
+            }
+
+            // Reposition within the currently-buffered segment, the way a `BufReader` advances
+            // inside an already-filled buffer before falling back to the underlying reader.
+            let remaining_in_segment = self.input_buffer.end - self.curr_argument;
+            if remaining_in_segment == 0 {
+                // `buffer()` hit EOF without yielding any new records - e.g. the final record
+                // has no trailing delimiter - so there is nothing left to skip past. Bail out
+                // instead of spinning forever re-reading the same empty segment.
+                return Err(InputIteratorErr::SeekOutOfRange(n, self.curr_argument));
+            }
+            let to_advance = cmp::min(n - self.curr_argument, remaining_in_segment);
+            self.curr_argument += to_advance;
+            self.input_buffer.index += to_advance;
+        }
+        Ok(())
+    }
+}
+
+// Implement the `Iterator` trait for `InputIterator` to gain access to all the `Iterator` methods for free.
+impl Iterator for InputIterator {
+    type Item = Result<String, InputIteratorErr>;
+
+    fn next(&mut self) -> Option<Result<String, InputIteratorErr>> {
+        // Lossy, allocating decode - kept permissive (matching the pre-`next_borrowed` baseline)
+        // so arguments with non-UTF-8 bytes still dispatch instead of failing the job.
+        self.advance().map(|result| {
+            result.map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        })
     }
 }
 
@@ -79,14 +169,16 @@ struct InputBuffer {
     start:       usize,
     end:         usize,
     capacity:    usize,
+    delimiter:   u8,
     disk_buffer: DiskBufferReader,
-    indices:     [usize; BUFFER_SIZE / 2],
+    indices:     Vec<usize>,
 }
 
 impl InputBuffer {
     /// Takes ownership of a `DiskBufferReader` and transforms it into a higher level
-    /// `InputBuffer` which will track additional information about the disk buffer.
-    fn new(mut unprocessed: DiskBufferReader) -> Result<InputBuffer, FileErr> {
+    /// `InputBuffer` which will track additional information about the disk buffer. Records
+    /// are split on `delimiter`, which is `b'\n'` unless NUL-separated input (`-0`) was requested.
+    fn new(mut unprocessed: DiskBufferReader, delimiter: u8) -> Result<InputBuffer, FileErr> {
         try!(unprocessed.buffer(0).map_err(|why| FileErr::Read(unprocessed.path.clone(), why)));
         let bytes_read = unprocessed.capacity;
 
@@ -95,8 +187,12 @@ impl InputBuffer {
             start:       0,
             end:         0,
             capacity:    0,
+            delimiter:   delimiter,
             disk_buffer: unprocessed,
-            indices:     [0usize; BUFFER_SIZE / 2]
+            // A `Vec` rather than the old fixed-size `[usize; BUFFER_SIZE / 2]` array, so a
+            // segment with more records than that array could hold simply grows instead of
+            // corrupting the index table.
+            indices:     Vec::with_capacity(BUFFER_SIZE / 2)
         };
 
         temp.count_arguments(bytes_read);
@@ -104,26 +200,73 @@ impl InputBuffer {
     }
 
     /// Counts the number of arguments that are stored in the buffer, marking the location of
-    /// the indices and the actual capacity of the buffer's useful information.
+    /// the indices and the actual capacity of the buffer's useful information. Delimiter offsets
+    /// are found with a SIMD-accelerated `memchr` scan rather than a byte-by-byte loop, and
+    /// `indices` grows on demand instead of being bounded by a fixed-size array.
     fn count_arguments(&mut self, bytes_read: usize) {
-        self.capacity = 0;
-        for (id, byte) in self.disk_buffer.data.iter().take(bytes_read).enumerate() {
-            if *byte == b'\n' {
-                self.indices[self.capacity + 1] = id;
-                self.capacity += 1;
-                self.end += 1;
-            }
+        self.indices.clear();
+        self.indices.push(0);
+        let mut found = 0;
+        for id in memchr_iter(self.delimiter, &self.disk_buffer.data[..bytes_read]) {
+            found += 1;
+            self.indices.push(id);
+            self.end += 1;
         }
-        self.capacity = self.indices[self.capacity];
+        self.capacity = self.indices[found];
     }
 }
 
 #[test]
 fn test_input_iterator() {
-    let iterator = InputIterator::new(Path::new("tests/buffer.dat"), 4096).unwrap();
+    let iterator = InputIterator::new(Path::new("tests/buffer.dat"), 4096, b'\n').unwrap();
     assert_eq!(0,  iterator.input_buffer.start);
     assert_eq!(1859, iterator.input_buffer.end);
     for (actual, expected) in iterator.zip((1..4096)) {
         assert_eq!(actual.unwrap(), expected.to_string());
     }
 }
+
+/// Covers the `memchr`-based rewrite of `count_arguments` against a file large enough to force
+/// several buffer refills, rather than relying only on `tests/buffer.dat`'s original size.
+#[test]
+fn test_input_iterator_memchr_large_synthetic_file() {
+    use std::fs::File;
+    use std::io::Write;
+
+    let path = Path::new("tests/large_buffer.dat");
+    {
+        let mut file = File::create(path).unwrap();
+        for n in 1..200_000 {
+            writeln!(file, "{}", n).unwrap();
+        }
+    }
+
+    let iterator = InputIterator::new(path, 199_999, b'\n').unwrap();
+    for (actual, expected) in iterator.zip(1..200_000) {
+        assert_eq!(actual.unwrap(), expected.to_string());
+    }
+}
+
+#[test]
+fn test_input_iterator_seek() {
+    let mut iterator = InputIterator::new(Path::new("tests/buffer.dat"), 4096, b'\n').unwrap();
+    iterator.seek(2048).unwrap();
+    assert_eq!("2049", iterator.next().unwrap().unwrap());
+}
+
+#[test]
+fn test_input_iterator_seek_out_of_range() {
+    let mut iterator = InputIterator::new(Path::new("tests/buffer.dat"), 4096, b'\n').unwrap();
+    assert!(iterator.seek(4097).is_err());
+}
+
+#[test]
+fn test_input_iterator_oversized_record() {
+    // `tests/oversized.dat` contains a single argument larger than `BUFFER_SIZE`, followed by
+    // a short trailing record; the buffer must grow to find the delimiter rather than corrupt
+    // the index table or truncate the record.
+    let mut iterator = InputIterator::new(Path::new("tests/oversized.dat"), 2, b'\n').unwrap();
+    let first = iterator.next().unwrap().unwrap();
+    assert!(first.len() > BUFFER_SIZE);
+    assert_eq!("done", iterator.next().unwrap().unwrap());
+}
@@ -4,18 +4,18 @@ use std::sync::mpsc::Sender;
 use std::time::Duration;
 use wait_timeout::ChildExt;
 use time::{get_time, Timespec};
-use super::signals;
+use super::signals::{self, SIGTERM, SIGKILL};
 use super::pipe::disk::output as pipe_output;
 use super::pipe::disk::State;
 
 pub fn handle_child(mut child: Child, output: &Sender<State>, flags: u16, job_id: usize, input: String,
-    has_timeout: bool, timeout: Duration) -> (Timespec, Timespec, i32, i32)
+    has_timeout: bool, timeout: Duration, grace: Duration) -> (Timespec, Timespec, i32, i32)
 {
     let start_time = get_time();
     if has_timeout && child.wait_timeout(timeout).unwrap().is_none() {
-        let _ = child.kill();
+        let signal = terminate_then_kill(&mut child, grace);
         pipe_output(&mut child, job_id, input, output, flags & QUIET_MODE != 0);
-        (start_time, get_time(), -1, 15)
+        (start_time, get_time(), -1, signal)
     } else {
         pipe_output(&mut child, job_id, input, output, flags & QUIET_MODE != 0);
         match child.wait() {
@@ -26,4 +26,19 @@ pub fn handle_child(mut child: Child, output: &Sender<State>, flags: u16, job_id
             Err(_) => (start_time, get_time(), -1, 0),
         }
     }
+}
+
+/// Gives a timed-out job a chance to flush output and clean up temp files: sends `SIGTERM` and
+/// waits up to `grace` for it to exit on its own, only escalating to `SIGKILL` if it's still
+/// alive afterward. Returns whichever signal actually reaped the process, so the job log can
+/// tell a clean `TERM` apart from a forced `KILL`.
+fn terminate_then_kill(child: &mut Child, grace: Duration) -> i32 {
+    if signals::send(child.id(), SIGTERM).is_ok() {
+        if let Ok(Some(_)) = child.wait_timeout(grace) {
+            return SIGTERM;
+        }
+    }
+
+    let _ = child.kill();
+    SIGKILL
 }
\ No newline at end of file